@@ -6,8 +6,8 @@
 //! use sync_wait_group::WaitGroup;
 //! use std::thread;
 //!
-//! // Create a new wait group.
-//! let wg = WaitGroup::new();
+//! // Create a new wait group, split into a waiter and an initial reference.
+//! let (waiter, wg) = WaitGroup::new();
 //!
 //! for _ in 0..4 {
 //!     // Create another reference to the wait group.
@@ -21,96 +21,354 @@
 //!     });
 //! }
 //!
-//! // Block until all threads have finished their work.
-//! wg.wait();
+//! // Drop our own reference, then block until every other reference has been dropped.
+//! drop(wg);
+//! waiter.wait();
 //! ```
+//!
+//! A [`Waiter`] can also be awaited from async tasks via [`Waiter::wait_async`], which resolves
+//! once every [`Ref`] has been dropped without blocking an OS thread.
+//!
+//! `WaitGroup` synchronizes exactly once: once every `Ref` has been dropped, it's spent. For
+//! repeated fan-out/fan-in phases over the same primitive, see [`ReusableWaitGroup`].
 
 use parking_lot::{Condvar, Mutex};
 use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
 
 /// Enables threads to synchronize the beginning or end of some computation.
-pub struct WaitGroup {
+///
+/// `WaitGroup` is a namespace for [`WaitGroup::new`], which splits the primitive into two
+/// disjoint halves: a [`Ref`], one of which every participant should hold and drop when its work
+/// is done, and a [`Waiter`], used only to block until every `Ref` has been dropped. Holding a
+/// `Waiter` never keeps the group alive, so a thread that only wants to observe completion can't
+/// accidentally prolong it.
+pub struct WaitGroup;
+
+/// Inner state shared between every `Ref` and `Waiter`.
+struct Inner {
+    cvar: Condvar,
+    state: Mutex<State>,
+}
+
+/// Shared counter and the async wakers blocked on it reaching zero.
+struct State {
+    count: usize,
+    wakers: Vec<Waker>,
+}
+
+impl WaitGroup {
+    /// Creates a new wait group, returning a `(Waiter, Ref)` pair.
+    ///
+    /// The returned `Ref` counts as the first outstanding participant; clone it once per
+    /// participant and drop each clone when that participant's work is done.
+    #[inline]
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> (Waiter, Ref) {
+        let inner = Arc::new(Inner {
+            cvar: Condvar::new(),
+            state: Mutex::new(State {
+                count: 1,
+                wakers: Vec::new(),
+            }),
+        });
+
+        (
+            Waiter {
+                inner: inner.clone(),
+            },
+            Ref { inner },
+        )
+    }
+}
+
+impl Inner {
+    /// Wakes every thread and task blocked on the count reaching zero.
+    #[inline]
+    fn notify_zero(&self, state: &mut State) {
+        self.cvar.notify_all();
+        for waker in state.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+/// A registered participant of a [`WaitGroup`].
+///
+/// Cloning a `Ref` registers another participant; dropping one retires it. Once every `Ref` has
+/// been dropped, any [`Waiter`] blocked on the group is woken.
+pub struct Ref {
     inner: Arc<Inner>,
 }
 
-/// Inner state of a `WaitGroup`.
-struct Inner {
+impl Ref {
+    /// Adds `delta` to the count of participants, in the style of Go's `sync.WaitGroup`.
+    ///
+    /// A positive `delta` registers that many additional participants; a negative one retires
+    /// them, waking any waiters once the count reaches zero. This lets a single `Ref` track an
+    /// arbitrary number of pending units of work without cloning it once per unit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this would make the count negative.
+    #[inline]
+    pub fn add(&self, delta: isize) {
+        let mut state = self.inner.state.lock();
+        let count = state.count as isize + delta;
+        assert!(count >= 0, "WaitGroup count would go negative");
+        state.count = count as usize;
+
+        if state.count == 0 {
+            self.inner.notify_zero(&mut state);
+        }
+    }
+
+    /// Retires one participant registered through [`add`](Ref::add). Equivalent to
+    /// `self.add(-1)`.
+    #[inline]
+    pub fn done(&self) {
+        self.add(-1);
+    }
+}
+
+impl Drop for Ref {
+    #[inline]
+    fn drop(&mut self) {
+        let mut state = self.inner.state.lock();
+        state.count -= 1;
+
+        if state.count == 0 {
+            self.inner.notify_zero(&mut state);
+        }
+    }
+}
+
+impl Clone for Ref {
+    #[inline]
+    fn clone(&self) -> Ref {
+        let mut state = self.inner.state.lock();
+        state.count += 1;
+
+        Ref {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for Ref {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.inner.state.lock().count;
+        f.debug_struct("Ref").field("count", &count).finish()
+    }
+}
+
+/// Blocks until every [`Ref`] of a [`WaitGroup`] has been dropped.
+///
+/// A `Waiter` is cheap to clone and holding one does not itself count as an outstanding
+/// participant, so it never keeps the group alive. `wait` may be called repeatedly and from
+/// multiple threads.
+#[derive(Clone)]
+pub struct Waiter {
+    inner: Arc<Inner>,
+}
+
+impl Waiter {
+    /// Blocks the current thread until every `Ref` has been dropped.
+    #[inline]
+    pub fn wait(&self) {
+        let mut state = self.inner.state.lock();
+        while state.count > 0 {
+            self.inner.cvar.wait(&mut state);
+        }
+    }
+
+    /// Returns a future that resolves once every `Ref` has been dropped.
+    ///
+    /// Unlike [`wait`](Waiter::wait), this doesn't block the calling thread, so it can be awaited
+    /// from within an async task.
+    #[inline]
+    pub fn wait_async(&self) -> WaitGroupFuture {
+        WaitGroupFuture {
+            inner: self.inner.clone(),
+        }
+    }
+
+    /// Blocks the current thread until every `Ref` has been dropped, or `timeout` elapses.
+    ///
+    /// Returns `true` if every `Ref` was dropped before the timeout, `false` otherwise.
+    #[inline]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let mut state = self.inner.state.lock();
+        let result = self
+            .inner
+            .cvar
+            .wait_while_for(&mut state, |state| state.count > 0, timeout);
+        !result.timed_out()
+    }
+
+    /// Blocks the current thread until every `Ref` has been dropped, or `deadline` passes.
+    ///
+    /// Returns `true` if every `Ref` was dropped before the deadline, `false` otherwise.
+    #[inline]
+    pub fn wait_deadline(&self, deadline: Instant) -> bool {
+        let mut state = self.inner.state.lock();
+        let result = self
+            .inner
+            .cvar
+            .wait_while_until(&mut state, |state| state.count > 0, deadline);
+        !result.timed_out()
+    }
+}
+
+impl fmt::Debug for Waiter {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.inner.state.lock().count;
+        f.debug_struct("Waiter").field("count", &count).finish()
+    }
+}
+
+/// Future returned by [`Waiter::wait_async`].
+///
+/// Resolves once every [`Ref`] of the wait group has been dropped.
+pub struct WaitGroupFuture {
+    inner: Arc<Inner>,
+}
+
+impl Future for WaitGroupFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.inner.state.lock();
+
+        if state.count == 0 {
+            return Poll::Ready(());
+        }
+
+        if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            state.wakers.push(cx.waker().clone());
+        }
+
+        Poll::Pending
+    }
+}
+
+/// Like [`WaitGroup`], but reusable across multiple synchronization phases, similar to a
+/// [`std::sync::Barrier`].
+///
+/// A plain `WaitGroup` is spent once its count reaches zero. `ReusableWaitGroup` instead tracks a
+/// generation counter alongside the count: when the count reaches zero, the generation advances
+/// and every waiter is released, and a clone taken afterwards begins the next generation with a
+/// fresh count. The handle returned by [`new`](ReusableWaitGroup::new) is a coordinator, not a
+/// participant itself — only its clones count towards the group, so it can be kept around and
+/// reused to dispatch and wait on as many generations as needed.
+pub struct ReusableWaitGroup {
+    inner: Arc<ReusableInner>,
+    // Whether this handle counts as an outstanding participant. `false` for the handle returned
+    // by `new`, `true` for every clone of it.
+    counted: bool,
+}
+
+struct ReusableInner {
     cvar: Condvar,
-    count: Mutex<usize>,
+    state: Mutex<ReusableState>,
 }
 
-impl Default for WaitGroup {
+struct ReusableState {
+    count: usize,
+    generation: u64,
+}
+
+impl Default for ReusableWaitGroup {
     #[inline]
     fn default() -> Self {
-        WaitGroup::new()
+        ReusableWaitGroup::new()
     }
 }
 
-impl WaitGroup {
-    /// Creates a new wait group and returns the single reference to it.
+impl ReusableWaitGroup {
+    /// Creates a new, empty reusable wait group.
     #[inline]
     pub fn new() -> Self {
         Self {
-            inner: Arc::new(Inner {
+            inner: Arc::new(ReusableInner {
                 cvar: Condvar::new(),
-                count: Mutex::new(1),
+                state: Mutex::new(ReusableState {
+                    count: 0,
+                    generation: 0,
+                }),
             }),
+            counted: false,
         }
     }
 
-    /// Drops this reference and waits until all other references are dropped.
+    /// Blocks the current thread until every participant registered in the current generation
+    /// has been dropped.
+    ///
+    /// Doesn't consume `self`, so it can be called again to wait on the next generation.
     #[inline]
-    pub fn wait(self) {
-        if *self.inner.count.lock() == 1 {
-            return;
-        }
+    pub fn wait(&self) {
+        let mut state = self.inner.state.lock();
+        let generation = state.generation;
 
-        let inner = self.inner.clone();
-        drop(self);
-
-        let mut count = inner.count.lock();
-        while *count > 0 {
-            inner.cvar.wait(&mut count);
+        while state.generation == generation && state.count > 0 {
+            self.inner.cvar.wait(&mut state);
         }
     }
 }
 
-impl Drop for WaitGroup {
+impl Drop for ReusableWaitGroup {
     #[inline]
     fn drop(&mut self) {
-        let mut count = self.inner.count.lock();
-        *count -= 1;
+        if !self.counted {
+            return;
+        }
 
-        if *count == 0 {
+        let mut state = self.inner.state.lock();
+        state.count -= 1;
+
+        if state.count == 0 {
+            state.generation = state.generation.wrapping_add(1);
             self.inner.cvar.notify_all();
         }
     }
 }
 
-impl Clone for WaitGroup {
+impl Clone for ReusableWaitGroup {
     #[inline]
-    fn clone(&self) -> WaitGroup {
-        let mut count = self.inner.count.lock();
-        *count += 1;
+    fn clone(&self) -> ReusableWaitGroup {
+        let mut state = self.inner.state.lock();
+        state.count += 1;
 
-        WaitGroup {
+        ReusableWaitGroup {
             inner: self.inner.clone(),
+            counted: true,
         }
     }
 }
 
-impl fmt::Debug for WaitGroup {
+impl fmt::Debug for ReusableWaitGroup {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let count: &usize = &*self.inner.count.lock();
-        f.debug_struct("WaitGroup").field("count", count).finish()
+        let state = self.inner.state.lock();
+        f.debug_struct("ReusableWaitGroup")
+            .field("count", &state.count)
+            .field("generation", &state.generation)
+            .finish()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::task::{RawWaker, RawWakerVTable, Waker};
     use std::thread;
     use std::time::Duration;
 
@@ -118,15 +376,15 @@ mod tests {
 
     #[test]
     fn wait() {
-        let wg = WaitGroup::new();
+        let (waiter, wg) = WaitGroup::new();
         let (tx, rx) = std::sync::mpsc::channel();
 
         for _ in 0..THREADS {
-            let wg = wg.clone();
+            let waiter = waiter.clone();
             let tx = tx.clone();
 
             thread::spawn(move || {
-                wg.wait();
+                waiter.wait();
                 tx.send(()).unwrap();
             });
         }
@@ -137,7 +395,8 @@ mod tests {
         // channel.
         assert!(rx.try_recv().is_err());
 
-        wg.wait();
+        drop(wg);
+        waiter.wait();
 
         // Now, the wait group is cleared and we should receive messages.
         for _ in 0..THREADS {
@@ -147,7 +406,7 @@ mod tests {
 
     #[test]
     fn wait_and_drop() {
-        let wg = WaitGroup::new();
+        let (waiter, wg) = WaitGroup::new();
         let (tx, rx) = std::sync::mpsc::channel();
 
         for _ in 0..THREADS {
@@ -165,11 +424,189 @@ mod tests {
         // channel.
         assert!(rx.try_recv().is_err());
 
-        wg.wait();
+        drop(wg);
+        waiter.wait();
+
+        // Now, the wait group is cleared and we should receive messages.
+        for _ in 0..THREADS {
+            rx.try_recv().unwrap();
+        }
+    }
+
+    #[test]
+    fn waiter_does_not_keep_group_alive() {
+        let (waiter, wg) = WaitGroup::new();
+
+        // Holding extra clones of the waiter must not block completion.
+        let _extra_waiter = waiter.clone();
+
+        drop(wg);
+        waiter.wait();
+    }
+
+    #[test]
+    fn wait_timeout_elapses() {
+        let (waiter, wg) = WaitGroup::new();
+
+        assert!(!waiter.wait_timeout(Duration::from_millis(50)));
+
+        drop(wg);
+    }
+
+    #[test]
+    fn wait_timeout_completes() {
+        let (waiter, wg) = WaitGroup::new();
+        let wg2 = wg.clone();
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            drop(wg2);
+        });
+
+        drop(wg);
+        assert!(waiter.wait_timeout(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn wait_deadline_elapses() {
+        let (waiter, wg) = WaitGroup::new();
+
+        assert!(!waiter.wait_deadline(Instant::now() + Duration::from_millis(50)));
+
+        drop(wg);
+    }
+
+    // A waker that just records whether it was woken, so tests can drive a `WaitGroupFuture`
+    // manually without depending on an async runtime.
+    fn woken_flag_waker() -> (Waker, Arc<AtomicBool>) {
+        fn clone(data: *const ()) -> RawWaker {
+            unsafe { Arc::increment_strong_count(data as *const AtomicBool) };
+            RawWaker::new(data, &VTABLE)
+        }
+        fn wake(data: *const ()) {
+            let flag = unsafe { Arc::from_raw(data as *const AtomicBool) };
+            flag.store(true, Ordering::SeqCst);
+        }
+        fn wake_by_ref(data: *const ()) {
+            let flag = unsafe { &*(data as *const AtomicBool) };
+            flag.store(true, Ordering::SeqCst);
+        }
+        fn drop_flag(data: *const ()) {
+            unsafe { Arc::from_raw(data as *const AtomicBool) };
+        }
+
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop_flag);
+
+        let flag = Arc::new(AtomicBool::new(false));
+        let raw = RawWaker::new(Arc::into_raw(flag.clone()) as *const (), &VTABLE);
+        (unsafe { Waker::from_raw(raw) }, flag)
+    }
+
+    #[test]
+    fn wait_async() {
+        let (waiter, wg) = WaitGroup::new();
+
+        let (waker, woken) = woken_flag_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut fut = waiter.wait_async();
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Pending);
+        assert!(!woken.load(Ordering::SeqCst));
+
+        drop(wg);
+
+        assert!(woken.load(Ordering::SeqCst));
+        assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Ready(()));
+    }
+
+    #[test]
+    fn add_and_done() {
+        let (waiter, wg) = WaitGroup::new();
+        wg.add(THREADS as isize);
+
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        for _ in 0..THREADS {
+            let wg = wg.clone();
+            let tx = tx.clone();
+
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(100));
+                tx.send(()).unwrap();
+                wg.done();
+                drop(wg);
+            });
+        }
+
+        // At this point, all spawned threads should be sleeping, so we shouldn't get anything from the
+        // channel.
+        assert!(rx.try_recv().is_err());
+
+        drop(wg);
+        waiter.wait();
 
         // Now, the wait group is cleared and we should receive messages.
         for _ in 0..THREADS {
             rx.try_recv().unwrap();
         }
     }
+
+    #[test]
+    #[should_panic(expected = "WaitGroup count would go negative")]
+    fn add_negative_panics() {
+        let (_waiter, wg) = WaitGroup::new();
+        wg.add(-2);
+    }
+
+    #[test]
+    fn reusable_wait_group_runs_multiple_generations() {
+        let wg = ReusableWaitGroup::new();
+
+        for _ in 0..3 {
+            let (tx, rx) = std::sync::mpsc::channel();
+
+            for _ in 0..THREADS {
+                let wg = wg.clone();
+                let tx = tx.clone();
+
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(50));
+                    tx.send(()).unwrap();
+                    drop(wg);
+                });
+            }
+
+            assert!(rx.try_recv().is_err());
+
+            wg.wait();
+
+            for _ in 0..THREADS {
+                rx.try_recv().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn reusable_wait_group_clone_after_completion_starts_next_generation() {
+        let wg = ReusableWaitGroup::new();
+        let wg2 = wg.clone();
+
+        drop(wg2);
+        wg.wait();
+
+        // The previous generation is done; this clone should start a fresh one rather than
+        // reusing the already-completed count, so `wait` must block until it's dropped.
+        let wg3 = wg.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            tx.send(()).unwrap();
+            drop(wg3);
+        });
+
+        assert!(rx.try_recv().is_err());
+        wg.wait();
+        rx.try_recv().unwrap();
+    }
 }